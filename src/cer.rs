@@ -0,0 +1,282 @@
+//! Canonical Encoding Rules (CER)
+//!
+//! CER is, like DER, a canonical subset of BER: there is exactly one valid
+//! CER encoding for a given value. It differs from DER in two ways (X.690
+//! section 9):
+//!
+//! - constructed values (`SEQUENCE`, `SET`, and constructed strings) always
+//!   use the *indefinite* length form, terminated by an end-of-contents
+//!   (EOC) marker (`0x00 0x00`), instead of a precomputed definite length;
+//! - primitive string types (`OCTET STRING`, `BIT STRING`, `UTF8String`, ...)
+//!   longer than 1000 octets are not encoded as a single primitive value.
+//!   Instead they are wrapped in a constructed, indefinite-length value
+//!   whose content is a sequence of primitive segments of at most 1000
+//!   octets each, followed by the EOC marker.
+//!
+//! [`CheckCerConstraints`] additionally requires `SET OF` elements to appear
+//! in their canonical (sorted) order, same as for DER.
+//!
+//! This module provides the building blocks shared by every [`ToCer`]/
+//! [`FromCer`] implementation, plus the `ToCer`/`CheckCerConstraints` impls
+//! for the primitive string types ([`OctetString`], [`BitString`],
+//! [`Utf8String`]) the chunking rule applies to. `FromCer` itself needs no
+//! per-type code beyond `CheckCerConstraints`: it is derived automatically,
+//! the same way [`crate::FromDer`] is derived from `CheckDerConstraints`.
+
+use crate::error::*;
+use crate::{Any, BitString, CheckCerConstraints, OctetString, Tagged, ToCer, ToDer, Utf8String};
+use std::io::Write;
+
+/// Maximum length, in octets, of a single primitive segment when chunking a
+/// long string value for CER (X.690 section 9.2).
+pub const MAX_SEGMENT_LENGTH: usize = 1000;
+
+/// The two-octet end-of-contents marker that terminates every
+/// indefinite-length value.
+pub const END_OF_CONTENTS: [u8; 2] = [0x00, 0x00];
+
+/// Writes the `0x80` byte that marks an indefinite-length header.
+///
+/// Used by `write_cer_header` implementations for constructed types instead
+/// of computing and writing a definite length.
+pub fn write_indefinite_length(writer: &mut dyn Write) -> SerializeResult<usize> {
+    writer.write_all(&[0x80])?;
+    Ok(1)
+}
+
+/// Writes the end-of-contents marker that closes an indefinite-length value.
+pub fn write_end_of_contents(writer: &mut dyn Write) -> SerializeResult<usize> {
+    writer.write_all(&END_OF_CONTENTS)?;
+    Ok(END_OF_CONTENTS.len())
+}
+
+/// Splits `content` into `MAX_SEGMENT_LENGTH`-sized chunks, writes each as a
+/// primitive value using `write_segment_header`, and terminates the whole
+/// thing with an end-of-contents marker.
+///
+/// Content of at most `MAX_SEGMENT_LENGTH` octets is written as a single
+/// primitive segment with no end-of-contents marker.
+///
+/// `write_segment_header` receives the segment length and must write the
+/// tag/length octets for that primitive segment (the universal tag of the
+/// underlying string type, e.g. OCTET STRING).
+pub fn write_chunked_content<F>(
+    writer: &mut dyn Write,
+    content: &[u8],
+    mut write_segment_header: F,
+) -> SerializeResult<usize>
+where
+    F: FnMut(&mut dyn Write, usize) -> SerializeResult<usize>,
+{
+    if content.len() <= MAX_SEGMENT_LENGTH {
+        let sz = write_segment_header(writer, content.len())?;
+        writer.write_all(content)?;
+        return Ok(sz + content.len());
+    }
+    let mut sz = 0;
+    for chunk in content.chunks(MAX_SEGMENT_LENGTH) {
+        sz += write_segment_header(writer, chunk.len())?;
+        writer.write_all(chunk)?;
+        sz += chunk.len();
+    }
+    sz += write_end_of_contents(writer)?;
+    Ok(sz)
+}
+
+/// Writes an identifier octet (or octets, in high-tag-number form).
+fn write_identifier(writer: &mut dyn Write, class_and_constructed: u8, tag: u32) -> SerializeResult<usize> {
+    if tag < 0x1f {
+        writer.write_all(&[class_and_constructed | tag as u8])?;
+        return Ok(1);
+    }
+    let mut buf = [0u8; 5];
+    let mut i = buf.len();
+    let mut n = tag;
+    loop {
+        i -= 1;
+        buf[i] = (n & 0x7f) as u8;
+        n >>= 7;
+        if n == 0 {
+            break;
+        }
+    }
+    writer.write_all(&[class_and_constructed | 0x1f])?;
+    let mut sz = 1;
+    let octets = &buf[i..];
+    for (idx, b) in octets.iter().enumerate() {
+        let byte = if idx + 1 < octets.len() { b | 0x80 } else { *b };
+        writer.write_all(&[byte])?;
+        sz += 1;
+    }
+    Ok(sz)
+}
+
+/// Writes a definite-length (short or long form) length field.
+fn write_definite_length(writer: &mut dyn Write, len: usize) -> SerializeResult<usize> {
+    if len < 0x80 {
+        writer.write_all(&[len as u8])?;
+        return Ok(1);
+    }
+    let bytes = len.to_be_bytes();
+    let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1);
+    let significant = &bytes[first_nonzero..];
+    writer.write_all(&[0x80 | significant.len() as u8])?;
+    writer.write_all(significant)?;
+    Ok(1 + significant.len())
+}
+
+/// `ToCer` for `OCTET STRING`: stays a single primitive value up to
+/// [`MAX_SEGMENT_LENGTH`] octets (identical to its DER encoding), and splits
+/// into chunked primitive segments wrapped in a constructed indefinite
+/// container beyond that.
+///
+/// # Examples
+///
+/// Below the 1000-octet threshold, the CER and DER encodings are identical:
+///
+/// ```
+/// use asn1_rs::{OctetString, ToCer, ToDer};
+///
+/// let s = OctetString::from(&b"hello"[..]);
+/// assert_eq!(s.to_cer_vec().unwrap(), s.to_der_vec().unwrap());
+/// ```
+impl ToCer for OctetString {
+    fn write_cer_header(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        if self.as_ref().len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_header(writer);
+        }
+        let a = write_identifier(writer, 0b0010_0000, <OctetString as Tagged>::TAG.0)?;
+        let b = write_indefinite_length(writer)?;
+        Ok(a + b)
+    }
+
+    fn write_cer_content(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        let content = self.as_ref();
+        if content.len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_content(writer);
+        }
+        let tag = <OctetString as Tagged>::TAG.0;
+        write_chunked_content(writer, content, |w, len| {
+            let a = write_identifier(w, 0, tag)?;
+            let b = write_definite_length(w, len)?;
+            Ok(a + b)
+        })
+    }
+}
+
+impl CheckCerConstraints for OctetString {
+    fn check_constraints(_any: &Any) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// `ToCer` for `BIT STRING`: same threshold as `OCTET STRING`, but chunked
+/// through [`write_chunked_bit_string`] rather than [`write_chunked_content`]
+/// — every primitive `BIT STRING` segment needs its own leading
+/// "number of unused bits" octet (X.690 8.6.2.2), which the shared
+/// string-chunking helper knows nothing about.
+///
+/// # Examples
+///
+/// Past the 1000-octet threshold, the value is wrapped in a constructed,
+/// indefinite-length container (terminated by the end-of-contents marker)
+/// instead of DER's single primitive value with a long-form length:
+///
+/// ```
+/// use asn1_rs::{BitString, ToCer, ToDer};
+///
+/// let data = vec![0xff; 1001];
+/// let s = BitString::new(3, &data);
+/// let cer = s.to_cer_vec().expect("serialization failed");
+/// let der = s.to_der_vec().expect("serialization failed");
+///
+/// assert_ne!(cer, der);
+/// assert_eq!(&cer[cer.len() - 2..], &[0x00, 0x00]);
+/// ```
+impl ToCer for BitString {
+    fn write_cer_header(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        if self.as_ref().len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_header(writer);
+        }
+        let a = write_identifier(writer, 0b0010_0000, <BitString as Tagged>::TAG.0)?;
+        let b = write_indefinite_length(writer)?;
+        Ok(a + b)
+    }
+
+    fn write_cer_content(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        let content = self.as_ref();
+        if content.len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_content(writer);
+        }
+        write_chunked_bit_string(writer, self.unused_bits, content)
+    }
+}
+
+/// Splits a `BIT STRING`'s data into primitive segments of at most
+/// `MAX_SEGMENT_LENGTH` content octets (the unused-bits octet plus up to
+/// `MAX_SEGMENT_LENGTH - 1` data octets) for CER, terminated by an
+/// end-of-contents marker.
+///
+/// Unlike [`write_chunked_content`], every segment is prefixed with its own
+/// "number of unused bits" octet: per X.690 8.6.2.2/9.2, only the final
+/// segment may report unused bits (the value's own `unused_bits`), every
+/// other segment's data ends on a full octet and reports zero.
+fn write_chunked_bit_string(
+    writer: &mut dyn Write,
+    unused_bits: u8,
+    content: &[u8],
+) -> SerializeResult<usize> {
+    let tag = <BitString as Tagged>::TAG.0;
+    let chunks: Vec<&[u8]> = content.chunks(MAX_SEGMENT_LENGTH - 1).collect();
+    let last_index = chunks.len() - 1;
+    let mut sz = 0;
+    for (i, chunk) in chunks.into_iter().enumerate() {
+        let segment_unused_bits = if i == last_index { unused_bits } else { 0 };
+        sz += write_identifier(writer, 0, tag)?;
+        sz += write_definite_length(writer, chunk.len() + 1)?;
+        writer.write_all(&[segment_unused_bits])?;
+        sz += 1;
+        writer.write_all(chunk)?;
+        sz += chunk.len();
+    }
+    sz += write_end_of_contents(writer)?;
+    Ok(sz)
+}
+
+impl CheckCerConstraints for BitString {
+    fn check_constraints(_any: &Any) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// `ToCer` for `UTF8String`: same chunking behaviour, operating on the
+/// string's UTF-8 bytes.
+impl ToCer for Utf8String {
+    fn write_cer_header(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        if self.as_ref().len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_header(writer);
+        }
+        let a = write_identifier(writer, 0b0010_0000, <Utf8String as Tagged>::TAG.0)?;
+        let b = write_indefinite_length(writer)?;
+        Ok(a + b)
+    }
+
+    fn write_cer_content(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        let content = self.as_ref().as_bytes();
+        if content.len() <= MAX_SEGMENT_LENGTH {
+            return self.write_der_content(writer);
+        }
+        let tag = <Utf8String as Tagged>::TAG.0;
+        write_chunked_content(writer, content, |w, len| {
+            let a = write_identifier(w, 0, tag)?;
+            let b = write_definite_length(w, len)?;
+            Ok(a + b)
+        })
+    }
+}
+
+impl CheckCerConstraints for Utf8String {
+    fn check_constraints(_any: &Any) -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+//! Declarative macro for thin tagged newtype wrappers
+//!
+//! A lot of application-specific ASN.1 types are just a single other type
+//! wearing a different tag, e.g. `[APPLICATION 5] IMPLICIT OCTET STRING`.
+//! Hand-writing `Tagged`, `TryFrom<Any>`, `CheckDerConstraints` and `ToDer`
+//! for each one is mechanical and repetitive. [`asn1_newtype`] generates all
+//! four from a single declaration.
+//!
+//! # Example
+//!
+//! ```
+//! use asn1_rs::*;
+//!
+//! // `[APPLICATION 5] IMPLICIT OCTET STRING`
+//! asn1_newtype!(pub struct MyOctetString(OctetString), implicit(Class::Application, 5));
+//!
+//! let s = MyOctetString(OctetString::from(&b"hello"[..]));
+//! let der = s.to_der_vec().expect("serialization failed");
+//! let (_, back) = MyOctetString::from_der(&der).expect("deserialization failed");
+//! assert_eq!(s.0.as_ref(), back.0.as_ref());
+//!
+//! // `[APPLICATION 6] EXPLICIT INTEGER`
+//! asn1_newtype!(pub struct MyInteger(Integer), explicit(Class::Application, 6));
+//!
+//! let i = MyInteger(Integer::from(4u32));
+//! let der = i.to_der_vec().expect("serialization failed");
+//! // EXPLICIT wraps the inner INTEGER's whole TLV (`02 01 04`, 3 octets)
+//! // as content, behind a 2-octet `[APPLICATION 6]` header.
+//! assert_eq!(der, vec![0x66, 0x03, 0x02, 0x01, 0x04]);
+//! assert_eq!(i.to_der_len().unwrap(), 3);
+//! let (_, back) = MyInteger::from_der(&der).expect("deserialization failed");
+//! assert_eq!(i.0, back.0);
+//! ```
+
+/// Generates `Tagged`, `TryFrom<Any>`, `CheckDerConstraints` and `ToDer` for
+/// a single-field tuple struct, forwarding to the field's own
+/// implementation through [`crate::AsTaggedImplicit`]/
+/// [`crate::AsTaggedExplicit`].
+///
+/// `DynTagged` is not generated here: it comes for free from the blanket
+/// `impl<T: Tagged> DynTagged for T` in [`crate::traits`].
+#[macro_export]
+macro_rules! asn1_newtype {
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($inner:ty), implicit($class:expr, $tag:expr)) => {
+        $(#[$meta])*
+        $vis struct $name(pub $inner);
+
+        impl $crate::Tagged for $name {
+            const TAG: $crate::Tag = $crate::Tag($tag);
+        }
+
+        impl<'a> ::std::convert::TryFrom<$crate::Any<'a>> for $name {
+            type Error = $crate::Error;
+
+            fn try_from(any: $crate::Any<'a>) -> ::std::result::Result<Self, Self::Error> {
+                // IMPLICIT only overrides the tag/class on the wire: the
+                // content is exactly $inner's. Re-tag the header back to
+                // $inner's own universal tag before handing it to $inner's
+                // `TryFrom`, which otherwise rejects it as a tag mismatch.
+                let mut any = any;
+                any.header.class = $crate::Class::Universal;
+                any.header.tag = <$inner as $crate::Tagged>::TAG;
+                <$inner as ::std::convert::TryFrom<$crate::Any<'a>>>::try_from(any).map($name)
+            }
+        }
+
+        impl $crate::CheckDerConstraints for $name {
+            fn check_constraints(any: &$crate::Any) -> $crate::Result<()> {
+                <$inner as $crate::CheckDerConstraints>::check_constraints(any)
+            }
+        }
+
+        impl $crate::ToDer for $name {
+            fn to_der_len(&self) -> $crate::Result<usize> {
+                self.0.to_der_len()
+            }
+
+            fn write_der_header(
+                &self,
+                writer: &mut dyn ::std::io::Write,
+            ) -> $crate::SerializeResult<usize> {
+                use $crate::AsTaggedImplicit;
+                // `structured` must match $inner's own constructed bit, not
+                // always primitive: peek at the bit $inner's own header
+                // would set rather than assuming it.
+                let mut probe = Vec::new();
+                self.0.write_der_header(&mut probe)?;
+                let constructed = probe.first().map_or(false, |b| b & 0b0010_0000 != 0);
+                (&self.0)
+                    .implicit($class, constructed as u8, $tag)
+                    .write_der_header(writer)
+            }
+
+            fn write_der_content(
+                &self,
+                writer: &mut dyn ::std::io::Write,
+            ) -> $crate::SerializeResult<usize> {
+                self.0.write_der_content(writer)
+            }
+        }
+    };
+
+    ($(#[$meta:meta])* $vis:vis struct $name:ident($inner:ty), explicit($class:expr, $tag:expr)) => {
+        $(#[$meta])*
+        $vis struct $name(pub $inner);
+
+        impl $crate::Tagged for $name {
+            const TAG: $crate::Tag = $crate::Tag($tag);
+        }
+
+        impl<'a> ::std::convert::TryFrom<$crate::Any<'a>> for $name {
+            type Error = $crate::Error;
+
+            fn try_from(any: $crate::Any<'a>) -> ::std::result::Result<Self, Self::Error> {
+                // EXPLICIT wraps a complete, independent TLV for $inner as
+                // this value's content: parse $inner out of that content
+                // instead of reinterpreting the outer `Any` (whose tag is
+                // this type's own override, not $inner's) as $inner itself.
+                let (_, inner) = <$inner as $crate::FromBer>::from_ber(any.as_bytes())
+                    .map_err(|e| e.into())?;
+                Ok($name(inner))
+            }
+        }
+
+        impl $crate::CheckDerConstraints for $name {
+            fn check_constraints(any: &$crate::Any) -> $crate::Result<()> {
+                // check $inner's own TLV, carried as this value's content
+                let (_, inner) = $crate::Any::from_der(any.as_bytes())
+                    .map_err(|e| -> $crate::Error { e.into() })?;
+                <$inner as $crate::CheckDerConstraints>::check_constraints(&inner)
+            }
+        }
+
+        impl $crate::ToDer for $name {
+            fn to_der_len(&self) -> $crate::Result<usize> {
+                // EXPLICIT wraps the inner value's whole TLV (header *and*
+                // content) as this value's content, so the length is that
+                // whole TLV's size — not `$inner::to_der_len()`, which is
+                // only its content length and leaves its own header
+                // uncounted.
+                let mut counter = $crate::writer::CountingWriter::default();
+                self.0
+                    .write_der(&mut counter)
+                    .map_err(::std::convert::Into::into)?;
+                Ok(counter.count())
+            }
+
+            fn write_der_header(
+                &self,
+                writer: &mut dyn ::std::io::Write,
+            ) -> $crate::SerializeResult<usize> {
+                use $crate::AsTaggedExplicit;
+                (&self.0).explicit($class, $tag).write_der_header(writer)
+            }
+
+            fn write_der_content(
+                &self,
+                writer: &mut dyn ::std::io::Write,
+            ) -> $crate::SerializeResult<usize> {
+                use $crate::AsTaggedExplicit;
+                (&self.0).explicit($class, $tag).write_der_content(writer)
+            }
+        }
+    };
+}
@@ -0,0 +1,364 @@
+//! Streaming BER/DER decoding over `std::io::Read`
+//!
+//! [`FromBer`]/[`FromDer`] parse zero-copy from a complete, in-memory `&[u8]`
+//! slice. That is not always an option: a message read off a socket or a
+//! large file may not be fully buffered up front. [`ReadBer`]/[`ReadDer`]
+//! provide the same decoding behaviour, but incrementally, pulling only as
+//! many bytes as each TLV needs from an arbitrary [`std::io::Read`] source.
+//!
+//! Because the source is no longer a borrowed slice, the decoded value can
+//! no longer borrow from it: implementors produce an owned value.
+//!
+//! Hostile input can claim a deeply nested structure or an enormous length
+//! field without actually sending that many bytes; [`LimitedReader`] wraps
+//! any `Read` with a maximum nesting depth and a maximum total byte budget
+//! so callers can bound the resources spent decoding input before it is
+//! trusted. [`read_tlv`] requires [`DepthLimit`] in addition to `Read` so
+//! that nesting depth is always enforced somehow, rather than being an
+//! opt-in a caller could forget — wrap a source that has no limit of its
+//! own in [`NoDepthLimit`] if that is genuinely what's wanted.
+
+use crate::error::*;
+use crate::{Class, Header, Length, Tag};
+use std::io::Read;
+
+/// Bounds recursion into nested indefinite-length values for [`read_tlv`].
+///
+/// [`LimitedReader`] enforces its configured `max_depth` here. [`NoDepthLimit`]
+/// is the explicit opt-out for sources that are already known to be
+/// trusted or shallow.
+pub trait DepthLimit {
+    /// Called when about to read an indefinite-length value's content, i.e.
+    /// descend one level deeper; returns an error if this would exceed
+    /// whatever depth budget is being enforced. Definite-length content is
+    /// read as raw bytes and does not call this.
+    fn enter(&mut self) -> Result<()>;
+
+    /// Called when done reading an indefinite-length value's content, i.e.
+    /// returning back up one level.
+    fn leave(&mut self);
+}
+
+/// Wraps any [`std::io::Read`] source to opt it out of [`read_tlv`]'s
+/// recursion-depth enforcement.
+///
+/// Prefer [`LimitedReader`] for input that has not already been bounded by
+/// some other means.
+#[derive(Debug)]
+pub struct NoDepthLimit<R>(pub R);
+
+impl<R: Read> Read for NoDepthLimit<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R: Read> DepthLimit for NoDepthLimit<R> {
+    fn enter(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn leave(&mut self) {}
+}
+
+/// Decode `Self` incrementally from a [`std::io::Read`] source, in the BER
+/// format.
+pub trait ReadBer: Sized {
+    fn read_ber<R: Read>(reader: &mut R) -> Result<Self>;
+}
+
+/// Decode `Self` incrementally from a [`std::io::Read`] source, in the DER
+/// format.
+pub trait ReadDer: Sized {
+    fn read_der<R: Read>(reader: &mut R) -> Result<Self>;
+}
+
+/// An owned identifier + length pair read from a `Read` source.
+///
+/// Mirrors [`Header`], but is produced by reading one octet at a time from a
+/// stream instead of being parsed out of a borrowed slice.
+#[derive(Debug, Clone)]
+pub struct OwnedHeader {
+    pub class: Class,
+    pub constructed: bool,
+    pub tag: Tag,
+    pub length: Length,
+}
+
+/// Reads the identifier octets (short or high-tag-number form) of a TLV from
+/// `reader`.
+fn read_identifier<R: Read>(reader: &mut R) -> Result<(Class, bool, Tag)> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte).map_err(|_| Error::InvalidTag)?;
+    let class = Class::try_from(byte[0] >> 6).map_err(|_| Error::InvalidClass)?;
+    let constructed = byte[0] & 0b0010_0000 != 0;
+    let low_tag = byte[0] & 0b0001_1111;
+    if low_tag != 0x1f {
+        return Ok((class, constructed, Tag(low_tag as u32)));
+    }
+    // high-tag-number form: base-128, MSB of each octet is a continuation bit
+    let mut tag: u32 = 0;
+    loop {
+        reader.read_exact(&mut byte).map_err(|_| Error::InvalidTag)?;
+        tag = (tag << 7) | (byte[0] & 0x7f) as u32;
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    Ok((class, constructed, Tag(tag)))
+}
+
+/// Reads the length octets (short, long, or indefinite form) of a TLV from
+/// `reader`.
+fn read_length<R: Read>(reader: &mut R) -> Result<Length> {
+    let mut byte = [0u8; 1];
+    reader.read_exact(&mut byte).map_err(|_| Error::InvalidLength)?;
+    if byte[0] == 0x80 {
+        return Ok(Length::Indefinite);
+    }
+    if byte[0] & 0x80 == 0 {
+        return Ok(Length::Definite(byte[0] as usize));
+    }
+    let num_octets = (byte[0] & 0x7f) as usize;
+    if num_octets > std::mem::size_of::<usize>() {
+        return Err(Error::InvalidLength);
+    }
+    let mut len: usize = 0;
+    for _ in 0..num_octets {
+        reader.read_exact(&mut byte).map_err(|_| Error::InvalidLength)?;
+        len = (len << 8) | byte[0] as usize;
+    }
+    Ok(Length::Definite(len))
+}
+
+/// How many bytes of a definite-length value are pulled from the reader in
+/// one go. Content longer than this is read and appended in successive
+/// chunks instead of being allocated as a single up-front buffer, so a
+/// crafted length field cannot force a single giant allocation before any
+/// byte budget (e.g. [`LimitedReader`]'s) gets a chance to reject it.
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// Reads exactly `len` bytes from `reader`, appending them to `out` in
+/// bounded chunks rather than allocating `len` bytes up front.
+fn read_bounded<R: Read>(reader: &mut R, len: usize, out: &mut Vec<u8>) -> Result<()> {
+    let mut chunk = [0u8; READ_CHUNK_SIZE];
+    let mut remaining = len;
+    while remaining > 0 {
+        let want = remaining.min(READ_CHUNK_SIZE);
+        reader
+            .read_exact(&mut chunk[..want])
+            .map_err(|_| Error::InvalidLength)?;
+        out.extend_from_slice(&chunk[..want]);
+        remaining -= want;
+    }
+    Ok(())
+}
+
+/// Reads one TLV's header and content bytes from `reader`.
+///
+/// For indefinite-length values, the content ends at the matching
+/// end-of-contents marker; nested TLVs are not unwrapped here, the raw
+/// content (including any nested indefinite-length markers) is returned
+/// as-is. Descending into an indefinite-length value's content counts as
+/// one level of nesting against `reader`'s [`DepthLimit`].
+///
+/// # Examples
+///
+/// ```
+/// use asn1_rs::reader::{read_tlv, LimitedReader};
+///
+/// // DER encoding of `INTEGER 4`
+/// let mut reader = LimitedReader::new(&[0x02, 0x01, 0x04][..], 16, 1024);
+/// let (header, content) = read_tlv(&mut reader).expect("read failed");
+///
+/// assert_eq!(header.tag.0, 2);
+/// assert_eq!(content, vec![0x04]);
+/// ```
+pub fn read_tlv<R: Read + DepthLimit>(reader: &mut R) -> Result<(OwnedHeader, Vec<u8>)> {
+    let (class, constructed, tag) = read_identifier(reader)?;
+    let length = read_length(reader)?;
+    let header = OwnedHeader {
+        class,
+        constructed,
+        tag,
+        length,
+    };
+    let mut content = Vec::new();
+    match length {
+        Length::Definite(len) => read_bounded(reader, len, &mut content)?,
+        Length::Indefinite => {
+            reader.enter()?;
+            let result = read_until_eoc(reader, &mut content);
+            reader.leave();
+            result?;
+        }
+    };
+    Ok((header, content))
+}
+
+/// Reads raw bytes up to (and consuming, but not including) the matching
+/// end-of-contents marker into `out`.
+///
+/// Unlike a byte-level scan for `0x00 0x00`/`0x80`, this actually parses
+/// each nested element's identifier and length so that arbitrary content
+/// bytes that happen to collide with those markers cannot desynchronize the
+/// scan: every element is consumed exactly as many bytes as its own length
+/// says it is, recursing for nested indefinite-length elements.
+fn read_until_eoc<R: Read + DepthLimit>(reader: &mut R, out: &mut Vec<u8>) -> Result<()> {
+    loop {
+        if read_element_or_eoc(reader, out)? {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads one nested element (identifier, length, content) and appends its
+/// raw encoding to `out`, or consumes an end-of-contents marker and returns
+/// `true` without appending anything.
+fn read_element_or_eoc<R: Read + DepthLimit>(reader: &mut R, out: &mut Vec<u8>) -> Result<bool> {
+    let mut id = [0u8; 1];
+    reader.read_exact(&mut id).map_err(|_| Error::InvalidTag)?;
+
+    // Tag number 0 in the universal class, primitive, is encoded as the
+    // single identifier octet 0x00; the byte that follows it is therefore
+    // always a length octet, never a second identifier octet. So `id[0] ==
+    // 0x00` can only be ambiguous with the end-of-contents marker, which is
+    // resolved by looking at that same next byte.
+    if id[0] == 0x00 {
+        let mut len_byte = [0u8; 1];
+        reader
+            .read_exact(&mut len_byte)
+            .map_err(|_| Error::InvalidLength)?;
+        if len_byte[0] == 0x00 {
+            return Ok(true);
+        }
+        out.push(id[0]);
+        out.push(len_byte[0]);
+        read_element_body(reader, len_byte[0], out)?;
+        return Ok(false);
+    }
+
+    out.push(id[0]);
+    if id[0] & 0x1f == 0x1f {
+        // high-tag-number form: base-128 continuation octets
+        loop {
+            let mut b = [0u8; 1];
+            reader.read_exact(&mut b).map_err(|_| Error::InvalidTag)?;
+            out.push(b[0]);
+            if b[0] & 0x80 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut len_byte = [0u8; 1];
+    reader
+        .read_exact(&mut len_byte)
+        .map_err(|_| Error::InvalidLength)?;
+    out.push(len_byte[0]);
+    read_element_body(reader, len_byte[0], out)?;
+    Ok(false)
+}
+
+/// Reads the content bytes of an element whose length octet(s) start with
+/// `len_byte`, appending them (and any further length octets) to `out`.
+fn read_element_body<R: Read + DepthLimit>(
+    reader: &mut R,
+    len_byte: u8,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    if len_byte & 0x80 == 0 {
+        // short form: len_byte itself is the length
+        return read_bounded(reader, len_byte as usize, out);
+    }
+    if len_byte == 0x80 {
+        // indefinite form: recurse, re-appending the EOC that closes it;
+        // `enter`/`leave` bound how many such nested levels are allowed
+        reader.enter()?;
+        let result = (|| -> Result<()> {
+            loop {
+                if read_element_or_eoc(reader, out)? {
+                    out.extend_from_slice(&[0x00, 0x00]);
+                    return Ok(());
+                }
+            }
+        })();
+        reader.leave();
+        return result;
+    }
+    // long form: `len_byte & 0x7f` further length octets follow
+    let num_octets = (len_byte & 0x7f) as usize;
+    if num_octets > std::mem::size_of::<usize>() {
+        return Err(Error::InvalidLength);
+    }
+    let mut len: usize = 0;
+    for _ in 0..num_octets {
+        let mut b = [0u8; 1];
+        reader.read_exact(&mut b).map_err(|_| Error::InvalidLength)?;
+        out.push(b[0]);
+        len = (len << 8) | b[0] as usize;
+    }
+    read_bounded(reader, len, out)
+}
+
+/// Wraps a [`std::io::Read`] source with hard limits on how many bytes and
+/// how many nested TLV levels a caller is willing to decode, so that a
+/// hostile length field or deeply nested structure cannot exhaust memory or
+/// the call stack before being rejected.
+pub struct LimitedReader<R> {
+    inner: R,
+    max_depth: usize,
+    max_len: usize,
+    depth: usize,
+    read: usize,
+}
+
+impl<R: Read> LimitedReader<R> {
+    /// Creates a new `LimitedReader` rejecting input with more than
+    /// `max_depth` nested indefinite-length levels or totaling more than
+    /// `max_len` bytes read.
+    pub fn new(inner: R, max_depth: usize, max_len: usize) -> Self {
+        LimitedReader {
+            inner,
+            max_depth,
+            max_len,
+            depth: 0,
+            read: 0,
+        }
+    }
+
+}
+
+impl<R: Read> DepthLimit for LimitedReader<R> {
+    /// Called by [`read_tlv`] when entering an indefinite-length value's
+    /// content; returns an error if this would exceed `max_depth`.
+    fn enter(&mut self) -> Result<()> {
+        if self.depth >= self.max_depth {
+            return Err(Error::InvalidLength);
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Called by [`read_tlv`] when leaving an indefinite-length value's
+    /// content.
+    fn leave(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+    }
+}
+
+impl<R: Read> Read for LimitedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.read >= self.max_len {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Other,
+                "LimitedReader: maximum byte budget exceeded",
+            ));
+        }
+        let remaining = self.max_len - self.read;
+        let cap = remaining.min(buf.len());
+        let n = self.inner.read(&mut buf[..cap])?;
+        self.read += n;
+        Ok(n)
+    }
+}
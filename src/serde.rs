@@ -0,0 +1,1077 @@
+//! Serde data-format bridge for BER/DER
+//!
+//! This module lets any type that derives `Serialize`/`Deserialize` be
+//! encoded to DER or decoded from BER through this crate, without having to
+//! hand-write [`ToDer`]/`TryFrom<Any>` implementations.
+//!
+//! Serde's data model is mapped onto ASN.1 as follows:
+//!
+//! - a struct (named or tuple) maps to a `SEQUENCE`
+//! - an enum variant maps to a `CHOICE`, encoded as a context-specific tag
+//!   carrying the variant's content
+//! - a sequence/slice maps to `SEQUENCE OF`
+//! - `Option::None` is omitted entirely (`OPTIONAL`); `Option::Some` wraps
+//!   its inner value in a reserved context-specific tag (see
+//!   [`Deserializer::deserialize_option`]) so a decoder walking a
+//!   `SEQUENCE`'s members positionally can tell "this field was omitted"
+//!   apart from "the next field's bytes happen to start here"
+//!
+//! [`ToDer::write_der_header`]/[`ToDer::write_der_content`] and
+//! [`Any::from_ber`] remain the byte-level engine; this module only adds the
+//! generic traversal that serde needs on top of them.
+//!
+//! Tag overrides are not supported: every value is written with the tag its
+//! serde data-model type maps to above. [`Serializer`] only receives serde
+//! data-model calls, so a value wrapped with
+//! [`crate::AsTaggedImplicit`]/[`crate::AsTaggedExplicit`] is not encoded
+//! with its `TaggedValue` tag.
+//!
+//! This module requires the `serde` feature.
+
+use crate::{Any, Boolean, Integer, OctetString, ToDer, Utf8String};
+use serde::{de, ser};
+use std::convert::TryFrom;
+use std::fmt;
+
+/// Error returned by the serde bridge.
+///
+/// Wraps the crate's own [`crate::error::Error`] so that codec failures and
+/// serde-level failures (missing fields, custom messages from derived impls)
+/// can share a single `Result` type across a `serialize`/`deserialize` call.
+#[derive(Debug)]
+pub enum Error {
+    /// An error returned by the underlying BER/DER codec
+    Asn1(crate::error::Error),
+    /// A message produced by `serde::de::Error::custom`/`ser::Error::custom`
+    Message(String),
+    /// The value does not have a representation in ASN.1 BER/DER
+    Unsupported(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Asn1(e) => write!(f, "{}", e),
+            Error::Message(msg) => f.write_str(msg),
+            Error::Unsupported(what) => write!(f, "{} has no ASN.1 DER representation", what),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<crate::error::Error> for Error {
+    fn from(e: crate::error::Error) -> Self {
+        Error::Asn1(e)
+    }
+}
+
+impl ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Context-specific, constructed tag number used to wrap a present
+/// `Option::Some` value (see module docs).
+///
+/// Chosen arbitrarily out of the 0-30 range that fits the identifier
+/// octet's short tag-number form. A `CHOICE` variant with this same index is
+/// written with the same identifier octet, so an absent `Option` field
+/// followed by a field encoded as that variant is decoded as a present
+/// `Some`. Leaf values, `SEQUENCE`s and `CHOICE` variants 0-29 never start
+/// with this octet.
+const OPTION_TAG: u32 = 30;
+
+/// The exact identifier octet [`OPTION_TAG`] is written as (context-specific,
+/// constructed, tag < 0x1f so it never needs the high-tag-number form).
+const OPTION_IDENTIFIER: u8 = 0b1010_0000 | OPTION_TAG as u8;
+
+/// Serialize `value` to a newly allocated DER-encoded buffer.
+///
+/// # Examples
+///
+/// ```
+/// use asn1_rs::serde::{from_ber_serde, to_der_serde};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct Point {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// let p = Point { x: 4, y: 7 };
+/// let der = to_der_serde(&p).expect("serialization failed");
+/// let back: Point = from_ber_serde(&der).expect("deserialization failed");
+/// assert_eq!(p, back);
+///
+/// // An absent `Option` field round-trips too, including when it isn't
+/// // the struct's last field: the middle `b` is omitted here, yet `c`
+/// // still decodes correctly instead of being consumed as `b`'s value.
+/// #[derive(Serialize, Deserialize, PartialEq, Debug)]
+/// struct WithOption {
+///     a: u32,
+///     b: Option<u32>,
+///     c: u32,
+/// }
+///
+/// let v = WithOption { a: 1, b: None, c: 2 };
+/// let der = to_der_serde(&v).expect("serialization failed");
+/// let back: WithOption = from_ber_serde(&der).expect("deserialization failed");
+/// assert_eq!(v, back);
+/// ```
+pub fn to_der_serde<T>(value: &T) -> Result<Vec<u8>>
+where
+    T: serde::Serialize,
+{
+    let mut serializer = Serializer::default();
+    value.serialize(&mut serializer)?;
+    Ok(serializer.content)
+}
+
+/// Parse a `T` from a BER-encoded buffer.
+pub fn from_ber_serde<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+    T: serde::Deserialize<'de>,
+{
+    let (_, any) = Any::from_ber(bytes).map_err(|e| Error::Asn1(e.into()))?;
+    let mut deserializer = Deserializer { any };
+    T::deserialize(&mut deserializer)
+}
+
+/// Writes the DER content of a serde value into an owned buffer.
+///
+/// Composite values (structs, sequences) recurse into a fresh `Serializer`
+/// so that their own content length is known before their header is
+/// written, mirroring how [`ToDer::write_der`] writes a header followed by
+/// content.
+#[derive(Default)]
+pub struct Serializer {
+    content: Vec<u8>,
+}
+
+impl Serializer {
+    fn write<T: ToDer>(&mut self, value: &T) -> Result<()> {
+        value
+            .write_der(&mut self.content)
+            .map_err(|e| Error::Asn1(e.into()))?;
+        Ok(())
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Serializer {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = SeqSerializer<'a>;
+    type SerializeMap = SeqSerializer<'a>;
+    type SerializeStruct = SeqSerializer<'a>;
+    type SerializeStructVariant = SeqSerializer<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.write(&Boolean::from(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.serialize_i64(v as i64)
+    }
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write(&Integer::from(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.serialize_u64(v as u64)
+    }
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write(&Integer::from(v))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<()> {
+        Err(Error::Unsupported("f32"))
+    }
+    fn serialize_f64(self, _v: f64) -> Result<()> {
+        Err(Error::Unsupported("f64"))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(&v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.write(&Utf8String::from(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.write(&OctetString::from(v))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        // OPTIONAL: absent values write nothing at all
+        Ok(())
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        // Wrap in `OPTION_TAG` instead of writing `value` unwrapped: an
+        // unwrapped encoding would be indistinguishable, on decode, from an
+        // omitted field immediately followed by the next field's bytes.
+        let mut inner = Serializer::default();
+        value.serialize(&mut inner)?;
+        inner.write_tagged(self, OPTION_TAG)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<()> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<()> {
+        // CHOICE with no content: an empty context-specific tag
+        write_context_header(&mut self.content, variant_index, 0);
+        Ok(())
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        // CHOICE: encode the variant as the content of a context-specific tag
+        let mut inner = Serializer::default();
+        value.serialize(&mut inner)?;
+        inner.write_tagged(self, variant_index)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Ok(SeqSerializer::new(self))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Ok(SeqSerializer::new_variant(self, variant_index, len))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Ok(SeqSerializer::new(self))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        _variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Ok(SeqSerializer::new_variant(self, variant_index, len))
+    }
+}
+
+/// Collects the serialized members of a `SEQUENCE`/`SEQUENCE OF` before
+/// writing the enclosing header, since [`ToDer`] needs the content length
+/// up front.
+pub struct SeqSerializer<'a> {
+    parent: &'a mut Serializer,
+    inner: Serializer,
+    variant_tag: Option<u32>,
+}
+
+impl<'a> SeqSerializer<'a> {
+    fn new(parent: &'a mut Serializer) -> Self {
+        SeqSerializer {
+            parent,
+            inner: Serializer::default(),
+            variant_tag: None,
+        }
+    }
+
+    fn new_variant(parent: &'a mut Serializer, variant_index: u32, _len: usize) -> Self {
+        SeqSerializer {
+            parent,
+            inner: Serializer::default(),
+            variant_tag: Some(variant_index),
+        }
+    }
+
+    fn finish(self) -> Result<()> {
+        match self.variant_tag {
+            None => {
+                // plain SEQUENCE: header carries the universal SEQUENCE tag
+                write_sequence_header(&mut self.parent.content, self.inner.content.len());
+                self.parent.content.extend_from_slice(&self.inner.content);
+            }
+            Some(tag) => {
+                self.inner.write_tagged(self.parent, tag)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Serializer {
+    /// Writes `self.content` as the content of a constructed,
+    /// context-specific value with tag number `tag` (used for CHOICE
+    /// variants and the [`OPTION_TAG`] wrapper).
+    fn write_tagged(self, parent: &mut Serializer, tag: u32) -> Result<()> {
+        write_context_header(&mut parent.content, tag, self.content.len());
+        parent.content.extend_from_slice(&self.content);
+        Ok(())
+    }
+}
+
+fn write_sequence_header(out: &mut Vec<u8>, len: usize) {
+    // SEQUENCE: universal class, constructed, tag number 16
+    write_identifier(out, 0b0010_0000, 16);
+    write_length(out, len);
+}
+
+fn write_context_header(out: &mut Vec<u8>, tag: u32, len: usize) {
+    // context-specific, constructed
+    write_identifier(out, 0b1010_0000, tag);
+    write_length(out, len);
+}
+
+fn write_identifier(out: &mut Vec<u8>, class_and_constructed: u8, tag: u32) {
+    if tag < 0x1f {
+        out.push(class_and_constructed | tag as u8);
+    } else {
+        out.push(class_and_constructed | 0x1f);
+        // base-128, high-tag-number form
+        let mut buf = [0u8; 5];
+        let mut i = buf.len();
+        let mut n = tag;
+        loop {
+            i -= 1;
+            buf[i] = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                break;
+            }
+        }
+        for (idx, b) in buf[i..].iter().enumerate() {
+            if idx + 1 < buf[i..].len() {
+                out.push(b | 0x80);
+            } else {
+                out.push(*b);
+            }
+        }
+    }
+}
+
+fn write_length(out: &mut Vec<u8>, len: usize) {
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes = len.to_be_bytes();
+        let first_nonzero = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len() - 1);
+        let significant = &bytes[first_nonzero..];
+        out.push(0x80 | significant.len() as u8);
+        out.extend_from_slice(significant);
+    }
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTuple for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeMap for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        key.serialize(&mut self.inner)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStruct for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for SeqSerializer<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + serde::Serialize,
+    {
+        value.serialize(&mut self.inner)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Walks an [`Any`] with a [`serde::Deserialize`] implementation.
+///
+/// Only the shapes produced by [`Serializer`] are understood: SEQUENCE for
+/// structs/sequences, a context-specific tag for CHOICE, and the matching
+/// universal primitive for each leaf type.
+pub struct Deserializer<'de> {
+    any: Any<'de>,
+}
+
+impl<'de, 'a> de::Deserializer<'de> for &'a mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(Error::Unsupported(
+            "self-describing deserialization (use a concrete type)",
+        ))
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let b = Boolean::try_from(self.any.clone()).map_err(Error::from)?;
+        visitor.visit_bool(b.bool())
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let i = Integer::try_from(self.any.clone()).map_err(Error::from)?;
+        visitor.visit_i64(i.as_i64().map_err(Error::from)?)
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let i = Integer::try_from(self.any.clone()).map_err(Error::from)?;
+        visitor.visit_u64(i.as_u64().map_err(Error::from)?)
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_f32<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(Error::Unsupported("f32"))
+    }
+    fn deserialize_f64<V>(self, _visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        Err(Error::Unsupported("f64"))
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let s = Utf8String::try_from(self.any.clone()).map_err(Error::from)?;
+        let c = s
+            .as_ref()
+            .chars()
+            .next()
+            .ok_or(Error::Unsupported("empty string as char"))?;
+        visitor.visit_char(c)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let s = Utf8String::try_from(self.any.clone()).map_err(Error::from)?;
+        visitor.visit_str(s.as_ref())
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let s = OctetString::try_from(self.any.clone()).map_err(Error::from)?;
+        visitor.visit_bytes(s.as_ref())
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // Reached directly (not through a `FieldDeserializer`), so this
+        // `Any` was already carved out for us: it exists, so it's `Some`.
+        // It is the `OPTION_TAG` wrapper written by `serialize_some`, so the
+        // value itself is the TLV inside it.
+        // `FieldDeserializer::deserialize_option` is what actually decides
+        // presence for fields nested in a SEQUENCE/struct.
+        if self.any.header.tag.0 != OPTION_TAG {
+            return Err(Error::Message("expected OPTIONAL wrapper".to_string()));
+        }
+        let (_, inner) = Any::from_ber(self.any.as_bytes()).map_err(|e| Error::Asn1(e.into()))?;
+        visitor.visit_some(&mut Deserializer { any: inner })
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(SeqAccess {
+            remaining: self.any.as_bytes(),
+        })
+    }
+
+    fn deserialize_tuple<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V>(self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_map(MapAccess {
+            remaining: self.any.as_bytes(),
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        // struct fields are laid out sequentially in the SEQUENCE content,
+        // same as a tuple: `visit_seq` is what every other positional binary
+        // format (bincode, postcard, ...) hands its derived `Visitor` too.
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_enum(EnumAccess {
+            any: self.any.clone(),
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        identifier ignored_any
+    }
+}
+
+/// Walks the back-to-back member values of a `SEQUENCE`/`SEQUENCE OF`.
+struct SeqAccess<'de> {
+    remaining: &'de [u8],
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess<'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        if self.remaining.is_empty() {
+            return Ok(None);
+        }
+        seed.deserialize(FieldDeserializer {
+            remaining: &mut self.remaining,
+        })
+        .map(Some)
+    }
+}
+
+/// Walks a flattened key, value, key, value, ... run the same way
+/// [`Serializer`]'s `SerializeMap` writes it.
+struct MapAccess<'de> {
+    remaining: &'de [u8],
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        if self.remaining.is_empty() {
+            return Ok(None);
+        }
+        seed.deserialize(FieldDeserializer {
+            remaining: &mut self.remaining,
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        seed.deserialize(FieldDeserializer {
+            remaining: &mut self.remaining,
+        })
+    }
+}
+
+/// Deserializer for a single `SEQUENCE`/map member, drawn lazily from a
+/// [`SeqAccess`]/[`MapAccess`]'s remaining bytes.
+///
+/// Unlike [`Deserializer`], this does not already hold a parsed [`Any`]: it
+/// defers consuming bytes from `remaining` until it knows, through which
+/// `deserialize_*` method serde ends up calling, whether the field being
+/// decoded is `Option<T>`. That is what lets
+/// [`deserialize_option`](de::Deserializer::deserialize_option) peek for the
+/// [`OPTION_IDENTIFIER`] wrapper without having already (mis-)consumed the
+/// next field's bytes for a field that turns out to be absent.
+struct FieldDeserializer<'a, 'de> {
+    remaining: &'a mut &'de [u8],
+}
+
+impl<'a, 'de> FieldDeserializer<'a, 'de> {
+    /// Parses and consumes the next `Any` from `remaining`.
+    fn next_any(&mut self) -> Result<Any<'de>> {
+        if self.remaining.is_empty() {
+            return Err(Error::Message("missing field".to_string()));
+        }
+        let (rest, any) = Any::from_ber(self.remaining).map_err(|e| Error::Asn1(e.into()))?;
+        *self.remaining = rest;
+        Ok(any)
+    }
+}
+
+/// Forwards `deserialize_*` methods that take no arguments besides the
+/// visitor to the equivalent method on a plain [`Deserializer`], after
+/// consuming the next `Any` from `remaining`.
+macro_rules! forward_after_next_any {
+    ($($method:ident),+ $(,)?) => {
+        $(
+            fn $method<V>(mut self, visitor: V) -> Result<V::Value>
+            where
+                V: de::Visitor<'de>,
+            {
+                let any = self.next_any()?;
+                (&mut Deserializer { any }).$method(visitor)
+            }
+        )+
+    };
+}
+
+impl<'a, 'de> de::Deserializer<'de> for FieldDeserializer<'a, 'de> {
+    type Error = Error;
+
+    forward_after_next_any! {
+        deserialize_any, deserialize_bool, deserialize_i8, deserialize_i16, deserialize_i32,
+        deserialize_i64, deserialize_i128, deserialize_u8, deserialize_u16, deserialize_u32,
+        deserialize_u64, deserialize_u128, deserialize_f32, deserialize_f64, deserialize_char,
+        deserialize_str, deserialize_string, deserialize_bytes, deserialize_byte_buf,
+        deserialize_unit, deserialize_seq, deserialize_map, deserialize_identifier,
+        deserialize_ignored_any,
+    }
+
+    /// Peeks at the next identifier octet to tell an absent field apart
+    /// from a present one, instead of unconditionally consuming whatever
+    /// `Any` is next (which may belong to a later, non-optional field).
+    fn deserialize_option<V>(mut self, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        if self.remaining.first() != Some(&OPTION_IDENTIFIER) {
+            return visitor.visit_none();
+        }
+        let wrapper = self.next_any()?;
+        let (_, inner) = Any::from_ber(wrapper.as_bytes()).map_err(|e| Error::Asn1(e.into()))?;
+        visitor.visit_some(&mut Deserializer { any: inner })
+    }
+
+    fn deserialize_unit_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V>(mut self, name: &'static str, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_newtype_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V>(mut self, len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_tuple_struct<V>(
+        mut self,
+        name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_tuple_struct(name, len, visitor)
+    }
+
+    fn deserialize_struct<V>(
+        mut self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V>(
+        mut self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        let any = self.next_any()?;
+        (&mut Deserializer { any }).deserialize_enum(name, variants, visitor)
+    }
+}
+
+/// Resolves the CHOICE variant from the context-specific tag number written
+/// by [`ser::Serializer::serialize_unit_variant`]/`..._variant`, then hands
+/// its content to a [`VariantAccessImpl`].
+struct EnumAccess<'de> {
+    any: Any<'de>,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess<'de> {
+    type Error = Error;
+    type Variant = VariantAccessImpl<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant)>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let tag = self.any.header.tag.0 as u64;
+        let variant = seed.deserialize(serde::de::value::U64Deserializer::<Error>::new(tag))?;
+        Ok((
+            variant,
+            VariantAccessImpl {
+                content: self.any.as_bytes(),
+            },
+        ))
+    }
+}
+
+struct VariantAccessImpl<'de> {
+    content: &'de [u8],
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccessImpl<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<()> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let (_, any) = Any::from_ber(self.content).map_err(|e| Error::Asn1(e.into()))?;
+        let mut deserializer = Deserializer { any };
+        seed.deserialize(&mut deserializer)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(SeqAccess {
+            remaining: self.content,
+        })
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: de::Visitor<'de>,
+    {
+        visitor.visit_seq(SeqAccess {
+            remaining: self.content,
+        })
+    }
+}
@@ -13,11 +13,17 @@ pub enum BerParser {}
 #[derive(Debug)]
 pub enum DerParser {}
 
+/// Phantom type representing a CER parser
+#[doc(hidden)]
+#[derive(Debug)]
+pub enum CerParser {}
+
 #[doc(hidden)]
 pub trait ASN1Parser {}
 
 impl ASN1Parser for BerParser {}
 impl ASN1Parser for DerParser {}
+impl ASN1Parser for CerParser {}
 
 pub trait Tagged {
     const TAG: Tag;
@@ -95,6 +101,39 @@ pub trait CheckDerConstraints {
     fn check_constraints(any: &Any) -> Result<()>;
 }
 
+/// Base trait for CER object parsers
+///
+/// Library authors should usually not directly implement this trait, but should prefer implementing the
+/// `TryFrom<Any>` + `CheckCerConstraints` traits,
+/// which offers greater flexibility and provides an equivalent `CerParser` implementation for free.
+pub trait FromCer<'a>: Sized {
+    fn from_cer(bytes: &'a [u8]) -> ParseResult<'a, Self>;
+}
+
+impl<'a, T> FromCer<'a> for T
+where
+    T: TryFrom<Any<'a>, Error = Error>,
+    T: CheckCerConstraints,
+{
+    fn from_cer(bytes: &'a [u8]) -> ParseResult<T> {
+        // unlike `FromDer`, CER explicitly allows (and for constructed types,
+        // requires) the indefinite length form, so it is not rejected here
+        let (i, any) = Any::from_ber(bytes)?;
+        <T as CheckCerConstraints>::check_constraints(&any).map_err(nom::Err::Failure)?;
+        let result = any.try_into().map_err(nom::Err::Failure)?;
+        Ok((i, result))
+    }
+}
+
+/// Verification of CER constraints
+///
+/// In addition to whatever a type's DER constraints require, CER requires
+/// constructed values to use the indefinite length form and `SET OF`
+/// elements to appear in their canonical (sorted) order.
+pub trait CheckCerConstraints {
+    fn check_constraints(any: &Any) -> Result<()>;
+}
+
 /// Common trait for all objects that can be encoded using the DER representation
 ///
 /// # Examples
@@ -174,6 +213,49 @@ where
     /// Attempt to write the DER content (all except header) to this writer.
     fn write_der_content(&self, writer: &mut dyn Write) -> SerializeResult<usize>;
 
+    /// Attempt to write the DER header for `content_len` bytes of content to
+    /// this writer, without going through `to_der_len`.
+    ///
+    /// [`write_der_buffered`](Self::write_der_buffered) already knows its
+    /// content's exact length (it just measured it), but `write_der_header`
+    /// always recomputes it via `to_der_len`; calling that would throw the
+    /// measurement away and, for a type whose `to_der_len` is itself derived
+    /// from `write_der_content` (see [`crate::writer::der_content_len`]),
+    /// run the content-writing logic a second time just to get a number it
+    /// already had.
+    ///
+    /// The default implementation does exactly that wasteful recomputation,
+    /// so it is always correct but never faster than `write_der_header`.
+    /// Override it for types whose header format only needs the length
+    /// (i.e. not `self`'s own state) to skip straight to writing the header
+    /// octets from `content_len`.
+    fn write_der_header_with_len(
+        &self,
+        writer: &mut dyn Write,
+        content_len: usize,
+    ) -> SerializeResult<usize> {
+        debug_assert_eq!(self.to_der_len().ok(), Some(content_len));
+        self.write_der_header(writer)
+    }
+
+    /// Write the DER header followed by content, without requiring
+    /// `to_der_len` to have been computed ahead of time.
+    ///
+    /// Writes the content into a scratch buffer first so its length is
+    /// known, then writes the header from that measured length via
+    /// [`write_der_header_with_len`](Self::write_der_header_with_len),
+    /// then the buffered content itself. This only avoids recomputing the
+    /// length for types that override `write_der_header_with_len`; with the
+    /// default implementation it is equivalent to [`write_der`](Self::write_der)
+    /// plus one extra buffer copy.
+    fn write_der_buffered(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        let mut buf = Vec::new();
+        let content_len = self.write_der_content(&mut buf)?;
+        let header_len = self.write_der_header_with_len(writer, content_len)?;
+        writer.write_all(&buf)?;
+        Ok(header_len + content_len)
+    }
+
     /// Similar to using `to_der`, but uses provided values without changes.
     /// This can generate an invalid encoding for a DER object.
     fn write_der_raw(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
@@ -199,6 +281,38 @@ where
     }
 }
 
+/// Common trait for all objects that can be encoded using the CER representation
+///
+/// CER differs from DER in that constructed values are always written using
+/// the indefinite length form (terminated by an end-of-contents marker),
+/// and long primitive string values are split into 1000-octet chunks
+/// wrapped in a constructed indefinite-length container. See [module-level
+/// documentation](crate::cer) for details.
+pub trait ToCer
+where
+    Self: DynTagged,
+{
+    /// Write the CER encoded representation (header and content) into this writer.
+    fn write_cer(&self, writer: &mut dyn Write) -> SerializeResult<usize> {
+        let sz = self.write_cer_header(writer)?;
+        let sz = sz + self.write_cer_content(writer)?;
+        Ok(sz)
+    }
+
+    /// Attempt to write the CER header to this writer.
+    fn write_cer_header(&self, writer: &mut dyn Write) -> SerializeResult<usize>;
+
+    /// Attempt to write the CER content (all except header) to this writer.
+    fn write_cer_content(&self, writer: &mut dyn Write) -> SerializeResult<usize>;
+
+    /// Write the CER encoded representation to a newly allocated `Vec<u8>`.
+    fn to_cer_vec(&self) -> SerializeResult<Vec<u8>> {
+        let mut v = Vec::new();
+        let _ = self.write_cer(&mut v)?;
+        Ok(v)
+    }
+}
+
 pub trait AsTaggedExplicit<'a>: Sized {
     fn explicit(self, class: Class, tag: u32) -> TaggedValue<'a, Explicit, Self> {
         TaggedValue::new_explicit(class, tag, self)
@@ -0,0 +1,61 @@
+//! Helpers for measuring DER content without writing it twice
+//!
+//! [`ToDer::to_der_len`] and [`ToDer::write_der_content`] are implemented
+//! separately, which means every type's size calculation has to be kept in
+//! sync by hand with whatever it actually writes. [`CountingWriter`] lets
+//! `to_der_len` be derived from `write_der_content` instead: write the
+//! content into a sink that only tallies bytes, and the tally is the length.
+//!
+//! See also [`ToDer::write_der_buffered`] and
+//! [`ToDer::write_der_header_with_len`], which use a scratch buffer instead
+//! of a counting sink to get the same length without running
+//! `write_der_content` twice — but only for types that override
+//! `write_der_header_with_len` to write their header straight from that
+//! length instead of recomputing it via `to_der_len`.
+
+use crate::{SerializeResult, ToDer};
+use std::io::Write;
+
+/// A [`std::io::Write`] sink that discards its input and only counts how
+/// many bytes were written to it.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CountingWriter {
+    count: usize,
+}
+
+impl CountingWriter {
+    /// Number of bytes written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Write for CountingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Derives a `to_der_len` result for `value` by writing its DER content
+/// into a [`CountingWriter`] and returning the tally, instead of
+/// implementing a separate length calculation.
+///
+/// # Examples
+///
+/// ```
+/// use asn1_rs::{Integer, ToDer};
+/// use asn1_rs::writer::der_content_len;
+///
+/// let int = Integer::from(4u32);
+/// assert_eq!(der_content_len(&int).unwrap(), 1);
+/// ```
+pub fn der_content_len<T: ToDer + ?Sized>(value: &T) -> SerializeResult<usize> {
+    let mut counter = CountingWriter::default();
+    value.write_der_content(&mut counter)?;
+    Ok(counter.count())
+}